@@ -1,53 +1,192 @@
+mod sha1;
+mod torrent;
+
+pub use torrent::{FileLayout, Torrent, TorrentError, TorrentFile, TorrentInfo};
+
 use std::collections::HashMap;
-use std::str::Chars;
+use std::error::Error;
+use std::fmt;
+use std::num::ParseIntError;
+use std::string::FromUtf8Error;
 
 #[derive(Debug, PartialEq)]
 pub enum BType {
-    ByteString(String),
+    ByteString(Vec<u8>),
     Integer(i64),
     List(Vec<BType>),
     Dict(HashMap<String, BType>)
 }
 
+/// The specific way a decode failed, independent of where it happened.
+#[derive(Debug, PartialEq)]
+pub enum BencodeErrorKind {
+    InputTooShort,
+    UnknownType(u8),
+    UnterminatedInteger,
+    BadInteger(ParseIntError),
+    ExpectedColon,
+    OddDictElements,
+    NonStringDictKey,
+    TrailingData
+}
+
+/// A decode failure, carrying the byte offset at which it occurred so
+/// callers can produce actionable diagnostics instead of matching on an
+/// opaque string.
+#[derive(Debug, PartialEq)]
+pub struct BencodeError {
+    pub kind: BencodeErrorKind,
+    pub offset: usize
+}
+
+impl BencodeError {
+    fn new(kind: BencodeErrorKind, offset: usize) -> Self {
+        BencodeError { kind, offset }
+    }
+}
+
+impl fmt::Display for BencodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.kind {
+            BencodeErrorKind::InputTooShort =>
+                write!(f, "input too short at byte {}", self.offset),
+            BencodeErrorKind::UnknownType(byte) =>
+                write!(f, "unknown type byte 0x{:02x} at byte {}", byte, self.offset),
+            BencodeErrorKind::UnterminatedInteger =>
+                write!(f, "unterminated integer starting at byte {}", self.offset),
+            BencodeErrorKind::BadInteger(err) =>
+                write!(f, "invalid integer at byte {}: {}", self.offset, err),
+            BencodeErrorKind::ExpectedColon =>
+                write!(f, "expected ':' after string length at byte {}", self.offset),
+            BencodeErrorKind::OddDictElements =>
+                write!(f, "dict has an odd number of elements at byte {}", self.offset),
+            BencodeErrorKind::NonStringDictKey =>
+                write!(f, "dict key is not a string at byte {}", self.offset),
+            BencodeErrorKind::TrailingData =>
+                write!(f, "trailing data after value at byte {}", self.offset)
+        }
+    }
+}
+
+impl Error for BencodeError {}
+
+impl BType {
+    /// Interprets a `ByteString` as UTF-8 text, for the common case where the
+    /// bytes are known to be text rather than arbitrary binary data (e.g. a
+    /// torrent's `pieces` field is not, but most other fields are).
+    pub fn as_utf8(&self) -> Option<Result<String, FromUtf8Error>> {
+        match self {
+            BType::ByteString(bytes) => Some(String::from_utf8(bytes.clone())),
+            _ => None
+        }
+    }
+
+    pub fn as_integer(&self) -> Option<i64> {
+        match self {
+            BType::Integer(number) => Some(*number),
+            _ => None
+        }
+    }
+
+    pub fn as_bytes(&self) -> Option<&[u8]> {
+        match self {
+            BType::ByteString(bytes) => Some(bytes),
+            _ => None
+        }
+    }
+
+    pub fn as_list(&self) -> Option<&[BType]> {
+        match self {
+            BType::List(items) => Some(items),
+            _ => None
+        }
+    }
+
+    pub fn as_dict(&self) -> Option<&HashMap<String, BType>> {
+        match self {
+            BType::Dict(dict) => Some(dict),
+            _ => None
+        }
+    }
+
+    /// Indexes into a dict, returning `None` if `self` is not a dict or the
+    /// key is absent.
+    pub fn get(&self, key: &str) -> Option<&BType> {
+        self.as_dict()?.get(key)
+    }
+
+    /// Walks nested dictionaries in one call, e.g. `t.path(&["info", "name"])`.
+    pub fn path(&self, keys: &[&str]) -> Option<&BType> {
+        keys.iter().try_fold(self, |value, key| value.get(key))
+    }
+}
+
 pub struct BEncoder;
 
 impl BEncoder {
-    pub fn decode(input: String) -> Result<BType, &'static str> {
+    /// Decodes a single bencoded value from the start of `input` and returns
+    /// it along with the number of bytes it consumed.
+    ///
+    /// Unlike `decode`, this does not require `input` to contain exactly one
+    /// value, so callers reading from a socket or a buffer holding several
+    /// back-to-back bencoded messages can repeatedly call `consume` and
+    /// advance by the returned offset rather than pre-splitting the input.
+    pub fn consume(input: &[u8]) -> Result<(usize, BType), BencodeError> {
         if input.len() < 2 {
-            return Err("Input string is too short.");
+            return Err(BencodeError::new(BencodeErrorKind::InputTooShort, 0));
         }
 
-        let mut chars = input.as_str().chars();
-        let mut cursor = chars.by_ref();
-        let next = cursor.next();
+        let mut index = 0;
+        let value = BEncoder::detect_and_decode(input, &mut index)?;
 
-        BEncoder::detect_and_decode(cursor, next)
+        Ok((index, value))
     }
 
-    fn detect_and_decode(cursor: &mut Chars, current: Option<char>) -> Result<BType, &'static str> {
-        match current {
+    pub fn decode(input: &[u8]) -> Result<BType, BencodeError> {
+        let (consumed, value) = BEncoder::consume(input)?;
+
+        if consumed != input.len() {
+            return Err(BencodeError::new(BencodeErrorKind::TrailingData, consumed));
+        }
+
+        Ok(value)
+    }
+
+    fn detect_and_decode(input: &[u8], index: &mut usize) -> Result<BType, BencodeError> {
+        match input.get(*index) {
             // Parse a dict
-            Some('d') => BEncoder::decode_dict(cursor),
+            Some(b'd') => {
+                *index += 1;
+                BEncoder::decode_dict(input, index)
+            },
             // Parse an integer
-            Some('i') => BEncoder::decode_integer(cursor),
+            Some(b'i') => {
+                *index += 1;
+                BEncoder::decode_integer(input, index)
+            },
             // Parse a list
-            Some('l') => BEncoder::decode_list(cursor),
+            Some(b'l') => {
+                *index += 1;
+                BEncoder::decode_list(input, index)
+            },
             // Parse a string
-            Some(chr) if chr.is_digit(10) => BEncoder::decode_string(chr, cursor),
-            _ => Err("Something is missing.")
+            Some(byte) if byte.is_ascii_digit() => BEncoder::decode_string(input, index),
+            Some(byte) => Err(BencodeError::new(BencodeErrorKind::UnknownType(*byte), *index)),
+            None => Err(BencodeError::new(BencodeErrorKind::InputTooShort, *index))
         }
     }
 
-    fn decode_dict(cursor: &mut Chars) -> Result<BType, &'static str> {
+    fn decode_dict(input: &[u8], index: &mut usize) -> Result<BType, BencodeError> {
         let mut elements = vec![];
 
-        let mut next = cursor.next();
+        while *index < input.len() {
+            if input[*index] == b'e' {
+                let closing_offset = *index;
+                *index += 1;
 
-        while next.is_some() {
-            if next == Some('e') {
                 // Check for base case
                 if elements.len() % 2 != 0 {
-                    return Err("Odd number of hash elements provided.");
+                    return Err(BencodeError::new(BencodeErrorKind::OddDictElements, closing_offset));
                 }
 
                 let mut acc = HashMap::new();
@@ -55,8 +194,11 @@ impl BEncoder {
                 while !elements.is_empty() {
                     let value = elements.pop().unwrap();
                     let key = match elements.pop() {
-                        Some(BType::ByteString(string)) => string,
-                        _ => return Err("Dict keys must be a string type.")
+                        Some(BType::ByteString(bytes)) => match String::from_utf8(bytes) {
+                            Ok(string) => string,
+                            Err(_) => return Err(BencodeError::new(BencodeErrorKind::NonStringDictKey, closing_offset))
+                        },
+                        _ => return Err(BencodeError::new(BencodeErrorKind::NonStringDictKey, closing_offset))
                     };
 
                     acc.insert(key, value);
@@ -65,87 +207,129 @@ impl BEncoder {
                 return Ok(BType::Dict(acc));
             }
 
-            let result = BEncoder::detect_and_decode(cursor, next);
-
-            if result.is_ok() {
-                elements.push(result.unwrap());
-            } else {
-                return result;
-            }
-
-            // Adv the cursor
-            next = cursor.next();
+            elements.push(BEncoder::detect_and_decode(input, index)?);
         }
 
-        Err("A list was not terminated with an 'e'.")
+        Err(BencodeError::new(BencodeErrorKind::InputTooShort, *index))
     }
 
-    fn decode_list(cursor: &mut Chars) -> Result<BType, &'static str> {
+    fn decode_list(input: &[u8], index: &mut usize) -> Result<BType, BencodeError> {
         let mut acc = vec![];
 
-        let mut next = cursor.next();
+        while *index < input.len() {
+            if input[*index] == b'e' {
+                *index += 1;
 
-        while next.is_some() {
-            if next == Some('e') {
                 // Check for base case (closed list)
-                return Ok(BType::List(acc))
+                return Ok(BType::List(acc));
             }
 
-            let result = BEncoder::detect_and_decode(cursor, next);
+            acc.push(BEncoder::detect_and_decode(input, index)?);
+        }
 
-            if result.is_ok() {
-                acc.push(result.unwrap());
-            } else {
-                return result;
-            }
+        Err(BencodeError::new(BencodeErrorKind::InputTooShort, *index))
+    }
+
+    fn decode_integer(input: &[u8], index: &mut usize) -> Result<BType, BencodeError> {
+        let start = *index;
+
+        while *index < input.len() && input[*index] != b'e' {
+            *index += 1;
+        }
 
-            // Adv the cursor
-            next = cursor.next();
+        if *index >= input.len() {
+            return Err(BencodeError::new(BencodeErrorKind::UnterminatedInteger, start));
         }
 
-        Err("A list was not terminated with an 'e'.")
+        let num_as_string = String::from_utf8_lossy(&input[start..*index]);
+
+        // Adv past the 'e'
+        *index += 1;
+
+        match num_as_string.parse::<i64>() {
+            Ok(integer) => Ok(BType::Integer(integer)),
+            Err(err) => Err(BencodeError::new(BencodeErrorKind::BadInteger(err), start))
+        }
     }
 
-    fn decode_integer(cursor: &mut Chars) -> Result<BType, &'static str> {
-        let mut current = '0';
-        let num_as_string = cursor
-            // HACK: This let's us keep track of the current
-            // position of the cursor.
-            .inspect(|x| current = x.clone())
-            .take_while(|chr| *chr != 'e').collect::<String>();
+    fn decode_string(input: &[u8], index: &mut usize) -> Result<BType, BencodeError> {
+        let start = *index;
 
-        let num_result = num_as_string.parse::<i64>();
+        while *index < input.len() && input[*index] != b':' {
+            *index += 1;
+        }
 
-        if current == 'e' {
-            if num_result.is_err() {
-                return Err("Error while parsing integer.");
-            }
+        if *index >= input.len() {
+            return Err(BencodeError::new(BencodeErrorKind::ExpectedColon, start));
+        }
+
+        let num_as_string = String::from_utf8_lossy(&input[start..*index]);
 
-            let integer = num_result.unwrap();
+        let number_of_bytes_to_read = match num_as_string.parse::<usize>() {
+            Ok(number) => number,
+            Err(err) => return Err(BencodeError::new(BencodeErrorKind::BadInteger(err), start))
+        };
 
-            Ok(BType::Integer(integer))
-        } else {
-            Err("No ending 'e' for integer.")
+        // Adv past the ':'
+        *index += 1;
+
+        if number_of_bytes_to_read > input.len().saturating_sub(*index) {
+            return Err(BencodeError::new(BencodeErrorKind::InputTooShort, *index));
         }
+
+        let bytes = input[*index..*index + number_of_bytes_to_read].to_vec();
+        *index += number_of_bytes_to_read;
+
+        Ok(BType::ByteString(bytes))
     }
 
-    fn decode_string(first: char, cursor: &mut Chars) -> Result<BType, &'static str> {
-        let appended_chrs = cursor.take_while(|chr| *chr != ':').collect::<String>();
+    /// Serializes a `BType` back into its canonical bencode representation.
+    ///
+    /// Dict keys are emitted in raw lexicographic (byte-wise) order, which is
+    /// what makes the output reproducible regardless of `HashMap` iteration
+    /// order (and is required by anything that later hashes the result).
+    pub fn encode(value: &BType) -> Vec<u8> {
+        let mut output = vec![];
 
-        let mut num_as_string = String::new();
-        num_as_string.push(first);
-        num_as_string.push_str(appended_chrs.as_str());
+        BEncoder::encode_into(value, &mut output);
 
-        let number_of_bytes_to_read_result = num_as_string.parse::<usize>();
+        output
+    }
 
+    fn encode_into(value: &BType, output: &mut Vec<u8>) {
+        match value {
+            BType::Integer(number) => {
+                output.push(b'i');
+                output.extend_from_slice(number.to_string().as_bytes());
+                output.push(b'e');
+            },
+            BType::ByteString(bytes) => {
+                output.extend_from_slice(bytes.len().to_string().as_bytes());
+                output.push(b':');
+                output.extend_from_slice(bytes);
+            },
+            BType::List(items) => {
+                output.push(b'l');
 
-        match number_of_bytes_to_read_result {
-            Ok(number_of_bytes_to_read) => {
-                let string = cursor.take(number_of_bytes_to_read).collect::<String>();
+                for item in items {
+                    BEncoder::encode_into(item, output);
+                }
 
-                Ok(BType::ByteString(string))
+                output.push(b'e');
             },
-            Err(_) => Err("Could not parse number for reading a string.")
+            BType::Dict(map) => {
+                output.push(b'd');
+
+                let mut entries: Vec<(&String, &BType)> = map.iter().collect();
+                entries.sort_by(|(a, _), (b, _)| a.as_bytes().cmp(b.as_bytes()));
+
+                for (key, value) in entries {
+                    BEncoder::encode_into(&BType::ByteString(key.clone().into_bytes()), output);
+                    BEncoder::encode_into(value, output);
+                }
+
+                output.push(b'e');
+            }
         }
     }
 }
@@ -155,18 +339,24 @@ mod tests {
     use std::collections::HashMap;
 
     use super::BEncoder;
+    use super::BencodeError;
+    use super::BencodeErrorKind;
     use super::BType;
 
+    fn bytes(string: &str) -> BType {
+        BType::ByteString(string.as_bytes().to_vec())
+    }
+
     #[test]
     fn it_errors_when_string_is_too_short() {
-        let result = BEncoder::decode("l".to_string());
+        let result = BEncoder::decode(b"l");
 
-        assert_eq!(result, Err("Input string is too short."));
+        assert_eq!(result, Err(BencodeError::new(BencodeErrorKind::InputTooShort, 0)));
     }
 
     #[test]
     fn it_can_parse_a_positive_integer() {
-        let result = BEncoder::decode("i123456789e".to_string());
+        let result = BEncoder::decode(b"i123456789e");
 
         assert!(result.is_ok());
         assert_eq!(result.unwrap(), BType::Integer(123456789));
@@ -174,7 +364,7 @@ mod tests {
 
     #[test]
     fn it_can_parse_a_negative_integer() {
-        let result = BEncoder::decode("i-123e".to_string());
+        let result = BEncoder::decode(b"i-123e");
 
         assert!(result.is_ok());
         assert_eq!(result.unwrap(), BType::Integer(-123));
@@ -182,23 +372,38 @@ mod tests {
 
     #[test]
     fn it_can_parse_a_string() {
-        let result = BEncoder::decode("5:hello".to_string());
+        let result = BEncoder::decode(b"5:hello");
 
         assert!(result.is_ok());
-        assert_eq!(result.unwrap(), BType::ByteString("hello".to_string()));
+        assert_eq!(result.unwrap(), bytes("hello"));
     }
 
     #[test]
     fn it_only_parses_the_number_of_bytes_specified() {
-        let result = BEncoder::decode("4:hello".to_string());
+        let result = BEncoder::consume(b"4:hello");
+
+        assert_eq!(result, Ok((6, bytes("hell"))));
+    }
+
+    #[test]
+    fn it_parses_non_utf8_byte_strings() {
+        let input = [b"3:".as_slice(), &[0xff, 0xfe, 0x00]].concat();
+        let result = BEncoder::decode(&input);
 
         assert!(result.is_ok());
-        assert_eq!(result.unwrap(), BType::ByteString("hell".to_string()));
+        assert_eq!(result.unwrap(), BType::ByteString(vec![0xff, 0xfe, 0x00]));
+    }
+
+    #[test]
+    fn it_does_not_overflow_on_a_huge_string_length_prefix() {
+        let result = BEncoder::decode(b"18446744073709551615:x");
+
+        assert_eq!(result, Err(BencodeError::new(BencodeErrorKind::InputTooShort, 21)));
     }
 
     #[test]
     fn it_can_parse_an_empty_list() {
-        let result = BEncoder::decode("le".to_string());
+        let result = BEncoder::decode(b"le");
 
         assert!(result.is_ok());
         assert_eq!(result.unwrap(), BType::List(vec![]));
@@ -206,37 +411,36 @@ mod tests {
 
     #[test]
     fn it_can_parse_a_basic_list() {
-        let result = BEncoder::decode("l5:helloe".to_string());
+        let result = BEncoder::decode(b"l5:helloe");
 
         assert!(result.is_ok());
-        assert_eq!(result.unwrap(),
-                   BType::List(vec![BType::ByteString("hello".to_string())]));
+        assert_eq!(result.unwrap(), BType::List(vec![bytes("hello")]));
     }
 
     #[test]
     fn it_can_parse_a_nested_list() {
-        let result = BEncoder::decode("ll5:helloee".to_string());
+        let result = BEncoder::decode(b"ll5:helloee");
 
         assert!(result.is_ok());
         assert_eq!(result.unwrap(),
-                   BType::List(vec![BType::List(vec![BType::ByteString("hello".to_string())])]));
+                   BType::List(vec![BType::List(vec![bytes("hello")])]));
     }
 
     #[test]
     fn it_can_parse_a_complex_list() {
-        let result = BEncoder::decode("ll5:helloei-10ee".to_string());
+        let result = BEncoder::decode(b"ll5:helloei-10ee");
 
         assert!(result.is_ok());
         assert_eq!(result.unwrap(),
                    BType::List(vec![
-                       BType::List(vec![BType::ByteString("hello".to_string())]),
+                       BType::List(vec![bytes("hello")]),
                        BType::Integer(-10)
                            ]));
     }
 
     #[test]
     fn it_can_parse_an_empty_dict() {
-        let result = BEncoder::decode("de".to_string());
+        let result = BEncoder::decode(b"de");
 
         assert!(result.is_ok());
         assert_eq!(result.unwrap(), BType::Dict(HashMap::new()));
@@ -244,11 +448,11 @@ mod tests {
 
     #[test]
     fn it_can_parse_a_simple_dict() {
-        let result = BEncoder::decode("d4:key16:value14:key26:value2e".to_string());
+        let result = BEncoder::decode(b"d4:key16:value14:key26:value2e");
 
         let mut example = HashMap::new();
-        example.insert("key1".to_string(), BType::ByteString("value1".to_string()));
-        example.insert("key2".to_string(), BType::ByteString("value2".to_string()));
+        example.insert("key1".to_string(), bytes("value1"));
+        example.insert("key2".to_string(), bytes("value2"));
 
         assert!(result.is_ok());
         assert_eq!(result.unwrap(), BType::Dict(example));
@@ -256,17 +460,165 @@ mod tests {
 
     #[test]
     fn it_can_parse_a_complex_dict() {
-        let result = BEncoder::decode("d4:key16:value14:key26:value22:okll5:helloei-10eee".to_string());
+        let result = BEncoder::decode(b"d4:key16:value14:key26:value22:okll5:helloei-10eee");
 
         let mut example = HashMap::new();
-        example.insert("key1".to_string(), BType::ByteString("value1".to_string()));
-        example.insert("key2".to_string(), BType::ByteString("value2".to_string()));
+        example.insert("key1".to_string(), bytes("value1"));
+        example.insert("key2".to_string(), bytes("value2"));
         example.insert("ok".to_string(), BType::List(vec![
-            BType::List(vec![BType::ByteString("hello".to_string())]),
+            BType::List(vec![bytes("hello")]),
             BType::Integer(-10)
                 ]));
 
         assert!(result.is_ok());
         assert_eq!(result.unwrap(), BType::Dict(example));
     }
+
+    #[test]
+    fn it_round_trips_an_integer() {
+        let value = BType::Integer(-10);
+        let encoded = BEncoder::encode(&value);
+
+        assert_eq!(BEncoder::decode(&encoded), Ok(value));
+    }
+
+    #[test]
+    fn it_round_trips_a_string() {
+        let value = bytes("hello");
+        let encoded = BEncoder::encode(&value);
+
+        assert_eq!(BEncoder::decode(&encoded), Ok(value));
+    }
+
+    #[test]
+    fn it_round_trips_a_nested_structure() {
+        let mut dict = HashMap::new();
+        dict.insert("key1".to_string(), bytes("value1"));
+        dict.insert("key2".to_string(), BType::Integer(42));
+        dict.insert("key3".to_string(), BType::List(vec![
+            bytes("a"),
+            BType::Integer(-1)
+        ]));
+
+        let value = BType::Dict(dict);
+        let encoded = BEncoder::encode(&value);
+
+        assert_eq!(BEncoder::decode(&encoded), Ok(value));
+    }
+
+    #[test]
+    fn it_encodes_dict_keys_in_lexicographic_order() {
+        let mut dict = HashMap::new();
+        dict.insert("b".to_string(), BType::Integer(2));
+        dict.insert("a".to_string(), BType::Integer(1));
+        dict.insert("c".to_string(), BType::Integer(3));
+
+        let encoded = BEncoder::encode(&BType::Dict(dict));
+
+        assert_eq!(encoded, b"d1:ai1e1:bi2e1:ci3ee");
+    }
+
+    #[test]
+    fn it_converts_a_byte_string_to_utf8() {
+        let value = bytes("hello");
+
+        assert_eq!(value.as_utf8(), Some(Ok("hello".to_string())));
+    }
+
+    #[test]
+    fn it_returns_none_for_as_utf8_on_non_byte_strings() {
+        assert_eq!(BType::Integer(1).as_utf8(), None);
+    }
+
+    #[test]
+    fn it_has_typed_accessors() {
+        assert_eq!(BType::Integer(5).as_integer(), Some(5));
+        assert_eq!(bytes("hi").as_bytes(), Some(b"hi".as_slice()));
+        assert_eq!(BType::List(vec![BType::Integer(1)]).as_list(), Some([BType::Integer(1)].as_slice()));
+        assert_eq!(BType::Integer(1).as_dict(), None);
+    }
+
+    #[test]
+    fn it_gets_a_value_out_of_a_dict_by_key() {
+        let mut dict = HashMap::new();
+        dict.insert("name".to_string(), bytes("tester"));
+
+        let value = BType::Dict(dict);
+
+        assert_eq!(value.get("name"), Some(&bytes("tester")));
+        assert_eq!(value.get("missing"), None);
+        assert_eq!(BType::Integer(1).get("name"), None);
+    }
+
+    #[test]
+    fn it_walks_nested_dicts_with_path() {
+        let mut inner = HashMap::new();
+        inner.insert("name".to_string(), bytes("tester"));
+
+        let mut outer = HashMap::new();
+        outer.insert("info".to_string(), BType::Dict(inner));
+
+        let root = BType::Dict(outer);
+
+        assert_eq!(root.path(&["info", "name"]), Some(&bytes("tester")));
+        assert_eq!(root.path(&["info", "missing"]), None);
+        assert_eq!(root.path(&["missing", "name"]), None);
+    }
+
+    #[test]
+    fn it_reports_the_offset_of_an_unknown_type() {
+        let result = BEncoder::decode(b"xxe");
+
+        assert_eq!(result, Err(BencodeError::new(BencodeErrorKind::UnknownType(b'x'), 0)));
+    }
+
+    #[test]
+    fn it_reports_the_offset_of_an_odd_dict() {
+        let result = BEncoder::decode(b"d4:key1e");
+
+        assert_eq!(result, Err(BencodeError::new(BencodeErrorKind::OddDictElements, 7)));
+    }
+
+    #[test]
+    fn it_reports_the_offset_of_an_unterminated_integer() {
+        let result = BEncoder::decode(b"i123");
+
+        assert_eq!(result, Err(BencodeError::new(BencodeErrorKind::UnterminatedInteger, 1)));
+    }
+
+    #[test]
+    fn it_displays_a_readable_message() {
+        let err = BencodeError::new(BencodeErrorKind::TrailingData, 4);
+
+        assert_eq!(err.to_string(), "trailing data after value at byte 4");
+    }
+
+    #[test]
+    fn it_rejects_trailing_data() {
+        let result = BEncoder::decode(b"i1ei2e");
+
+        assert_eq!(result, Err(BencodeError::new(BencodeErrorKind::TrailingData, 3)));
+    }
+
+    #[test]
+    fn it_consumes_only_the_first_value_and_reports_bytes_read() {
+        let result = BEncoder::consume(b"i1ei2e");
+
+        assert_eq!(result, Ok((3, BType::Integer(1))));
+    }
+
+    #[test]
+    fn it_consumes_back_to_back_messages() {
+        let input = b"i1ei2ei3e";
+        let mut offset = 0;
+        let mut values = vec![];
+
+        while offset < input.len() {
+            let (consumed, value) = BEncoder::consume(&input[offset..]).unwrap();
+            values.push(value);
+            offset += consumed;
+        }
+
+        assert_eq!(values, vec![BType::Integer(1), BType::Integer(2), BType::Integer(3)]);
+    }
 }
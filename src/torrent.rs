@@ -0,0 +1,271 @@
+//! Parsing of `.torrent` metainfo files on top of the raw bencode decoder,
+//! including the `info_hash` computation the BitTorrent protocol relies on.
+
+use std::error::Error;
+use std::fmt;
+
+use crate::sha1;
+use crate::{BEncoder, BencodeError, BencodeErrorKind, BType};
+
+#[derive(Debug, PartialEq)]
+pub enum TorrentError {
+    Decode(BencodeError),
+    MissingField(&'static str),
+    WrongFieldType(&'static str),
+    InvalidPiecesLength
+}
+
+impl fmt::Display for TorrentError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TorrentError::Decode(err) => write!(f, "could not decode torrent: {}", err),
+            TorrentError::MissingField(field) => write!(f, "missing field '{}'", field),
+            TorrentError::WrongFieldType(field) => write!(f, "field '{}' has the wrong type", field),
+            TorrentError::InvalidPiecesLength => write!(f, "'pieces' length is not a multiple of 20")
+        }
+    }
+}
+
+impl Error for TorrentError {}
+
+impl From<BencodeError> for TorrentError {
+    fn from(err: BencodeError) -> Self {
+        TorrentError::Decode(err)
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Torrent {
+    pub announce: String,
+    pub announce_list: Option<Vec<Vec<String>>>,
+    pub info: TorrentInfo,
+    pub info_hash: [u8; 20]
+}
+
+#[derive(Debug, PartialEq)]
+pub struct TorrentInfo {
+    pub name: String,
+    pub piece_length: i64,
+    pub pieces: Vec<[u8; 20]>,
+    pub layout: FileLayout
+}
+
+#[derive(Debug, PartialEq)]
+pub enum FileLayout {
+    SingleFile { length: i64 },
+    MultiFile { files: Vec<TorrentFile> }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct TorrentFile {
+    pub path: Vec<String>,
+    pub length: i64
+}
+
+impl Torrent {
+    /// Parses a `.torrent` metainfo file and computes its `info_hash`.
+    ///
+    /// The BitTorrent spec defines the hash as the SHA-1 digest over the
+    /// *exact original bencoded bytes* of the `info` dictionary, not a
+    /// re-encoding of it, so the raw byte range is located in `input`
+    /// directly rather than recomputed from the parsed `BType`.
+    pub fn parse(input: &[u8]) -> Result<Torrent, TorrentError> {
+        let root = BEncoder::decode(input)?;
+
+        if root.as_dict().is_none() {
+            return Err(TorrentError::WrongFieldType("root"));
+        }
+
+        let announce = required_string(&root, "announce")?;
+        let announce_list = match root.get("announce-list") {
+            Some(value) => Some(parse_announce_list(value)?),
+            None => None
+        };
+
+        let info_value = root.get("info").ok_or(TorrentError::MissingField("info"))?;
+        let info = TorrentInfo::parse(info_value)?;
+
+        let (info_start, info_end) = info_span(input)?
+            .ok_or(TorrentError::MissingField("info"))?;
+        let info_hash = sha1::sha1(&input[info_start..info_end]);
+
+        Ok(Torrent { announce, announce_list, info, info_hash })
+    }
+}
+
+impl TorrentInfo {
+    fn parse(value: &BType) -> Result<TorrentInfo, TorrentError> {
+        if value.as_dict().is_none() {
+            return Err(TorrentError::WrongFieldType("info"));
+        }
+
+        let name = required_string(value, "name")?;
+        let piece_length = required_integer(value, "piece length")?;
+
+        let pieces = match value.get("pieces") {
+            Some(entry) => split_into_hashes(entry.as_bytes().ok_or(TorrentError::WrongFieldType("pieces"))?)?,
+            None => return Err(TorrentError::MissingField("pieces"))
+        };
+
+        let layout = if let Some(length) = value.get("length").and_then(BType::as_integer) {
+            FileLayout::SingleFile { length }
+        } else if let Some(files) = value.get("files").and_then(BType::as_list) {
+            let files = files.iter().map(TorrentFile::parse).collect::<Result<Vec<_>, _>>()?;
+            FileLayout::MultiFile { files }
+        } else {
+            return Err(TorrentError::MissingField("length or files"));
+        };
+
+        Ok(TorrentInfo { name, piece_length, pieces, layout })
+    }
+}
+
+impl TorrentFile {
+    fn parse(value: &BType) -> Result<TorrentFile, TorrentError> {
+        if value.as_dict().is_none() {
+            return Err(TorrentError::WrongFieldType("files"));
+        }
+
+        let length = required_integer(value, "length")?;
+
+        let path = value.get("path")
+            .and_then(BType::as_list)
+            .ok_or(TorrentError::MissingField("path"))?
+            .iter()
+            .map(|segment| required_utf8(segment, "path"))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(TorrentFile { path, length })
+    }
+}
+
+fn required_string(value: &BType, field: &'static str) -> Result<String, TorrentError> {
+    match value.get(field) {
+        Some(entry) => required_utf8(entry, field),
+        None => Err(TorrentError::MissingField(field))
+    }
+}
+
+fn required_integer(value: &BType, field: &'static str) -> Result<i64, TorrentError> {
+    match value.get(field) {
+        Some(entry) => entry.as_integer().ok_or(TorrentError::WrongFieldType(field)),
+        None => Err(TorrentError::MissingField(field))
+    }
+}
+
+fn required_utf8(value: &BType, field: &'static str) -> Result<String, TorrentError> {
+    value.as_bytes()
+        .ok_or(TorrentError::WrongFieldType(field))
+        .and_then(|bytes| String::from_utf8(bytes.to_vec()).map_err(|_| TorrentError::WrongFieldType(field)))
+}
+
+fn split_into_hashes(bytes: &[u8]) -> Result<Vec<[u8; 20]>, TorrentError> {
+    if !bytes.len().is_multiple_of(20) {
+        return Err(TorrentError::InvalidPiecesLength);
+    }
+
+    Ok(bytes.chunks(20).map(|chunk| {
+        let mut hash = [0u8; 20];
+        hash.copy_from_slice(chunk);
+        hash
+    }).collect())
+}
+
+fn parse_announce_list(value: &BType) -> Result<Vec<Vec<String>>, TorrentError> {
+    value.as_list()
+        .ok_or(TorrentError::WrongFieldType("announce-list"))?
+        .iter()
+        .map(|tier| tier.as_list()
+            .ok_or(TorrentError::WrongFieldType("announce-list"))?
+            .iter()
+            .map(|url| required_utf8(url, "announce-list"))
+            .collect())
+        .collect()
+}
+
+/// Walks the top-level metainfo dict by hand (rather than via the already
+/// fully-decoded `BType::Dict`) so it can report the raw byte range of the
+/// `info` value's encoding, as found in `input`.
+fn info_span(input: &[u8]) -> Result<Option<(usize, usize)>, BencodeError> {
+    let mut index = match input.first() {
+        Some(b'd') => 1,
+        Some(byte) => return Err(BencodeError::new(BencodeErrorKind::UnknownType(*byte), 0)),
+        None => return Err(BencodeError::new(BencodeErrorKind::InputTooShort, 0))
+    };
+
+    while index < input.len() && input[index] != b'e' {
+        let (key_len, key) = BEncoder::consume(&input[index..])?;
+        index += key_len;
+
+        let value_start = index;
+        let (value_len, _) = BEncoder::consume(&input[index..])?;
+        index += value_len;
+
+        if key == BType::ByteString(b"info".to_vec()) {
+            return Ok(Some((value_start, index)));
+        }
+    }
+
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn synthetic_torrent() -> Vec<u8> {
+        let mut torrent = b"d8:announce18:http://tracker.com4:info".to_vec();
+        torrent.extend_from_slice(b"d6:lengthi12e4:name4:test12:piece lengthi16384e6:pieces20:");
+        torrent.extend_from_slice(&[b'A'; 20]);
+        torrent.extend_from_slice(b"ee");
+        torrent
+    }
+
+    #[test]
+    fn it_parses_a_single_file_torrent() {
+        let torrent = Torrent::parse(&synthetic_torrent()).unwrap();
+
+        assert_eq!(torrent.announce, "http://tracker.com");
+        assert_eq!(torrent.announce_list, None);
+        assert_eq!(torrent.info.name, "test");
+        assert_eq!(torrent.info.piece_length, 16384);
+        assert_eq!(torrent.info.pieces, vec![[b'A'; 20]]);
+        assert_eq!(torrent.info.layout, FileLayout::SingleFile { length: 12 });
+    }
+
+    #[test]
+    fn it_computes_the_info_hash_over_the_raw_info_bytes() {
+        let torrent = Torrent::parse(&synthetic_torrent()).unwrap();
+
+        assert_eq!(
+            torrent.info_hash,
+            [
+                0x21, 0x03, 0xac, 0x0a, 0x89, 0x85, 0xda, 0x1b, 0x54, 0x6e,
+                0xac, 0x54, 0xf5, 0x32, 0xea, 0x8a, 0x3a, 0xdc, 0x46, 0x12
+            ]
+        );
+    }
+
+    #[test]
+    fn it_errors_when_info_is_missing() {
+        let result = Torrent::parse(b"d8:announce18:http://tracker.come");
+
+        assert_eq!(result, Err(TorrentError::MissingField("info")));
+    }
+
+    #[test]
+    fn it_errors_when_info_is_not_a_dict() {
+        let result = Torrent::parse(b"d8:announce18:http://tracker.com4:infoi5ee");
+
+        assert_eq!(result, Err(TorrentError::WrongFieldType("info")));
+    }
+
+    #[test]
+    fn it_errors_when_pieces_length_is_not_a_multiple_of_20() {
+        let mut torrent = b"d8:announce18:http://tracker.com4:info".to_vec();
+        torrent.extend_from_slice(b"d6:lengthi12e4:name4:test12:piece lengthi16384e6:pieces3:abce");
+        torrent.push(b'e');
+
+        assert_eq!(Torrent::parse(&torrent), Err(TorrentError::InvalidPiecesLength));
+    }
+}